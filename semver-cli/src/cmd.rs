@@ -1,10 +1,73 @@
 extern crate clap;
-use clap::Parser;
-use semver::DisplayOptions;
+use clap::{ArgEnum, Parser, Subcommand};
+use semver::{DisplayFormat, DisplayOptions, RollingTag};
+
+#[derive(ArgEnum, Clone, Debug)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+    Alpha,
+    Beta,
+    Rc,
+}
+
+#[derive(ArgEnum, Clone, Debug)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(ArgEnum, Clone, Debug)]
+pub enum Scheme {
+    Semver,
+    Pep440,
+}
+
+#[derive(ArgEnum, Clone, Debug)]
+pub enum Only {
+    Major,
+    Minor,
+    Patch,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print the next version, bumped according to the given part
+    Bump {
+        #[clap(arg_enum)]
+        part: Bump,
+
+        /// Version to bump
+        version: String,
+    },
+
+    /// Compare two versions by SemVer precedence, printing -1, 0 or 1
+    Compare {
+        /// First version
+        v1: String,
+
+        /// Second version
+        v2: String,
+    },
+
+    /// Check whether a version satisfies a requirement, exiting 0 if it does
+    /// and 1 otherwise
+    Satisfies {
+        /// Requirement, e.g. "^1.2.3" or ">=1.2.3, <2.0.0"
+        req: String,
+
+        /// Version to check
+        version: String,
+    },
+}
 
 #[derive(Parser, Debug)]
 #[clap(name = "semver")]
 pub struct Semver {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+
     /// Should remove the 'v' prefix
     #[clap(short, long)]
     pub remove_v_prefix: bool,
@@ -17,8 +80,36 @@ pub struct Semver {
     #[clap(short, long)]
     pub single_line: bool,
 
-    /// Version to be parsed
-    pub version: String,
+    /// Print the next version instead, bumped according to the given part
+    #[clap(short, long, arg_enum)]
+    pub bump: Option<Bump>,
+
+    /// Check whether the version matches a requirement (e.g. "^1.2"), exiting
+    /// 0 if it does and 1 otherwise
+    #[clap(short, long)]
+    pub matches: Option<String>,
+
+    /// Output format
+    #[clap(short, long, arg_enum, default_value = "text")]
+    pub format: Format,
+
+    /// Shorthand for --format json.
+    ///
+    /// Emits the same object as `--format json`: `major`/`minor`/`patch`/
+    /// `prerelease`/`build`/`prefix` plus `is_prerelease`.
+    #[clap(long)]
+    pub json: bool,
+
+    /// Versioning scheme to parse the version against
+    #[clap(long, arg_enum, default_value = "semver")]
+    pub scheme: Scheme,
+
+    /// Only print the given rolling tag instead of all of them
+    #[clap(short, long, arg_enum)]
+    pub only: Option<Only>,
+
+    /// Version to be parsed, when no subcommand is used
+    pub version: Option<String>,
 }
 
 impl From<Semver> for DisplayOptions {
@@ -27,6 +118,19 @@ impl From<Semver> for DisplayOptions {
             prefix: item.prefix,
             remove_v_prefix: item.remove_v_prefix,
             single_line: item.single_line,
+            format: if item.json {
+                DisplayFormat::Json
+            } else {
+                match item.format {
+                    Format::Text => DisplayFormat::Text,
+                    Format::Json => DisplayFormat::Json,
+                }
+            },
+            only: item.only.map(|only| match only {
+                Only::Major => RollingTag::Major,
+                Only::Minor => RollingTag::Minor,
+                Only::Patch => RollingTag::Patch,
+            }),
         }
     }
 }
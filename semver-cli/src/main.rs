@@ -1,10 +1,70 @@
+use anyhow::Context;
 use clap::Parser;
-use semver::{parse, DisplayOptions};
+use semver::{parse, req, DisplayOptions, Semver};
 
 pub mod cmd;
 
+fn bump(version: &Semver, part: &cmd::Bump) -> anyhow::Result<Semver> {
+    Ok(match part {
+        cmd::Bump::Major => version.increment_major(),
+        cmd::Bump::Minor => version.increment_minor(),
+        cmd::Bump::Patch => version.increment_patch(),
+        cmd::Bump::Alpha => version.increment_alpha()?,
+        cmd::Bump::Beta => version.increment_beta()?,
+        cmd::Bump::Rc => version.increment_rc()?,
+    })
+}
+
 fn main() -> anyhow::Result<()> {
     let opts = cmd::Semver::parse();
-    parse(&opts.version)?.print(DisplayOptions::from(opts));
+
+    if let Some(cmd::Command::Bump { part, version }) = &opts.command {
+        println!("{}", bump(&parse(version)?, part)?);
+        return Ok(());
+    }
+
+    if let Some(cmd::Command::Compare { v1, v2 }) = &opts.command {
+        // `Ord` on `Semver` ignores build metadata for precedence, so e.g.
+        // `compare 1.0.0+a 1.0.0` and `compare 1.0.0+a 1.0.0+b` both print `0`.
+        let ordering = parse(v1)?.cmp(&parse(v2)?);
+        let (code, exit_code) = match ordering {
+            std::cmp::Ordering::Less => (-1, 1),
+            std::cmp::Ordering::Equal => (0, 0),
+            std::cmp::Ordering::Greater => (1, 2),
+        };
+        println!("{code}");
+        std::process::exit(exit_code);
+    }
+
+    if let Some(cmd::Command::Satisfies { req, version }) = &opts.command {
+        let matches = req::parse(req)?.matches(&parse(version)?);
+        println!("{matches}");
+        std::process::exit(!matches as i32);
+    }
+
+    let version_str = opts
+        .version
+        .as_deref()
+        .context("the following required arguments were not provided: <VERSION>")?;
+
+    if let cmd::Scheme::Pep440 = opts.scheme {
+        println!("{}", semver::pep440::parse(version_str)?);
+        return Ok(());
+    }
+
+    let version = parse(version_str)?;
+
+    if let Some(version_req) = &opts.matches {
+        let matches = req::parse(version_req)?.matches(&version);
+        println!("{matches}");
+        std::process::exit(!matches as i32);
+    }
+
+    let version = match &opts.bump {
+        Some(part) => bump(&version, part)?,
+        None => version,
+    };
+
+    version.print(DisplayOptions::from(opts));
     Ok(())
 }
@@ -1,8 +1,11 @@
-use anyhow::Context;
+use anyhow::{bail, Context};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::str::FromStr;
 
+pub mod pep440;
+pub mod req;
+
 lazy_static! {
     static ref RE: Regex = Regex::new(
         r"^(?x)v?
@@ -26,40 +29,226 @@ pub struct Semver {
     buildmetadata: Option<String>,
 }
 
+impl Semver {
+    /// Major version component
+    pub fn major(&self) -> u128 {
+        self.major
+    }
+
+    /// Minor version component
+    pub fn minor(&self) -> u128 {
+        self.minor
+    }
+
+    /// Patch version component
+    pub fn patch(&self) -> u128 {
+        self.patch
+    }
+
+    /// Prerelease identifier, if any (e.g. `alpha.1` in `1.2.3-alpha.1`)
+    pub fn prerelease(&self) -> Option<&str> {
+        self.prerelease.as_deref()
+    }
+
+    /// Build metadata, if any (e.g. `meta` in `1.2.3+meta`)
+    pub fn buildmetadata(&self) -> Option<&str> {
+        self.buildmetadata.as_deref()
+    }
+
+    /// Check if it is a prerelease version
+    pub fn is_prerelease(&self) -> bool {
+        self.prerelease.is_some()
+    }
+
+    fn canonical(&self) -> String {
+        let mut s = format!("{}.{}.{}", self.major, self.minor, self.patch);
+        if let Some(prerelease) = &self.prerelease {
+            s.push('-');
+            s.push_str(prerelease);
+        }
+        if let Some(buildmetadata) = &self.buildmetadata {
+            s.push('+');
+            s.push_str(buildmetadata);
+        }
+        s
+    }
+
+    /// Build a `Semver` from its components, validating the prerelease and
+    /// buildmetadata identifiers against the same rules the parser's regex
+    /// enforces.
+    pub fn new(
+        major: u128,
+        minor: u128,
+        patch: u128,
+        prerelease: Option<String>,
+        buildmetadata: Option<String>,
+    ) -> anyhow::Result<Self> {
+        if let Some(prerelease) = &prerelease {
+            validate_prerelease(prerelease)?;
+        }
+        if let Some(buildmetadata) = &buildmetadata {
+            validate_buildmetadata(buildmetadata)?;
+        }
+
+        Ok(Semver {
+            major,
+            minor,
+            patch,
+            prerelease,
+            buildmetadata,
+        })
+    }
+}
+
+impl std::fmt::Display for Semver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.canonical())
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-'
+}
+
+/// A prerelease identifier is either numeric (no leading zeros, unless it is
+/// exactly `0`) or alphanumeric (allowing leading zeros).
+fn validate_prerelease(prerelease: &str) -> anyhow::Result<()> {
+    for identifier in prerelease.split('.') {
+        if identifier.is_empty() || !identifier.chars().all(is_identifier_char) {
+            bail!("invalid prerelease identifier: `{identifier}`");
+        }
+        if identifier.chars().all(|c| c.is_ascii_digit())
+            && identifier.len() > 1
+            && identifier.starts_with('0')
+        {
+            bail!("prerelease identifier must not have a leading zero: `{identifier}`");
+        }
+    }
+    Ok(())
+}
+
+/// A buildmetadata identifier is alphanumeric, leading zeros allowed.
+fn validate_buildmetadata(buildmetadata: &str) -> anyhow::Result<()> {
+    for identifier in buildmetadata.split('.') {
+        if identifier.is_empty() || !identifier.chars().all(is_identifier_char) {
+            bail!("invalid buildmetadata identifier: `{identifier}`");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Semver {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.canonical())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Semver {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 impl PartialEq for Semver {
     fn eq(&self, other: &Self) -> bool {
+        // Build metadata is not significant to precedence per the spec, and
+        // `Ord` below agrees: two versions equal-up-to-build-metadata are `==`.
         self.major == other.major
             && self.minor == other.minor
             && self.patch == other.patch
             && self.prerelease.as_deref() == other.prerelease.as_deref()
-            && self.buildmetadata.as_deref() == other.buildmetadata.as_deref()
     }
 }
 
 impl PartialOrd for Semver {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Semver {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch)) {
             std::cmp::Ordering::Equal => {}
-            ord => return Some(ord),
+            ord => return ord,
         }
 
-        // if prerelease or buildmetadata are different, they are not comparable
-        match self.prerelease.partial_cmp(&other.prerelease) {
-            Some(core::cmp::Ordering::Equal) => {}
-            _ord => return None,
+        // build metadata is ignored for ordering (and equality) purposes
+        match (&self.prerelease, &other.prerelease) {
+            (None, None) => std::cmp::Ordering::Equal,
+            // a version with a prerelease has lower precedence than one without
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (Some(a), Some(b)) => compare_prerelease(a, b),
         }
-        match self.buildmetadata.partial_cmp(&other.buildmetadata) {
-            Some(core::cmp::Ordering::Equal) => Some(core::cmp::Ordering::Equal),
-            _ord => None,
+    }
+}
+
+/// Compare two prerelease strings identifier-by-identifier, per the SemVer
+/// precedence rules: numeric identifiers are compared numerically and always
+/// rank below alphanumeric ones, alphanumeric identifiers compare ASCII-lexically,
+/// and a prefix with fewer identifiers ranks lower when all shared ones are equal.
+fn compare_prerelease(a: &str, b: &str) -> std::cmp::Ordering {
+    let mut a_ids = a.split('.');
+    let mut b_ids = b.split('.');
+
+    loop {
+        match (a_ids.next(), b_ids.next()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(a_id), Some(b_id)) => {
+                let a_numeric = a_id.bytes().all(|c| c.is_ascii_digit());
+                let b_numeric = b_id.bytes().all(|c| c.is_ascii_digit());
+                let ord = match (a_numeric, b_numeric) {
+                    // numeric identifiers have no leading zeros, so the longer
+                    // digit string is the larger number, whatever its size
+                    (true, true) => a_id.len().cmp(&b_id.len()).then_with(|| a_id.cmp(b_id)),
+                    (true, false) => std::cmp::Ordering::Less,
+                    (false, true) => std::cmp::Ordering::Greater,
+                    (false, false) => a_id.cmp(b_id),
+                };
+                if ord != std::cmp::Ordering::Equal {
+                    return ord;
+                }
+            }
         }
     }
 }
 
-#[derive(Debug)]
+/// Output format selected via `DisplayOptions::format`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Which of the rolling tags (`major`, `major.minor`, `major.minor.patch`) to
+/// print for a release version, selected via `DisplayOptions::only`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollingTag {
+    Major,
+    Minor,
+    Patch,
+}
+
+#[derive(Debug, Default)]
 pub struct DisplayOptions {
     pub prefix: String,
     pub remove_v_prefix: bool,
     pub single_line: bool,
+    pub format: DisplayFormat,
+    pub only: Option<RollingTag>,
 }
 
 impl Semver {
@@ -71,15 +260,58 @@ impl Semver {
             prefix.push('v');
         }
 
+        if opts.format == DisplayFormat::Json {
+            return self.print_json(&prefix);
+        }
+
         if opts.single_line {
-            self.print_single_line(prefix);
+            self.print_single_line(prefix, opts.only);
         } else {
-            self.print_multiple_lines(prefix);
+            self.print_multiple_lines(prefix, opts.only);
         }
     }
 
+    /// The rolling tags to print for a release version, filtered by `only`
+    fn rolling_tags(&self, prefix: &str, only: Option<RollingTag>) -> Vec<String> {
+        let tags = [
+            (RollingTag::Major, format!("{prefix}{}", self.major)),
+            (
+                RollingTag::Minor,
+                format!("{prefix}{}.{}", self.major, self.minor),
+            ),
+            (
+                RollingTag::Patch,
+                format!("{prefix}{}.{}.{}", self.major, self.minor, self.patch),
+            ),
+        ];
+
+        tags.into_iter()
+            .filter(|(tag, _)| only.is_none() || only == Some(*tag))
+            .map(|(_, tag)| tag)
+            .collect()
+    }
+
+    /// Print as a single-line JSON object with every parsed field.
+    ///
+    /// Keys are `major`, `minor`, `patch`, `prerelease`, `build` and `prefix`,
+    /// matching the schema the request specified, plus `is_prerelease` kept
+    /// from chunk0-4's earlier additions. `--json` (added later as a
+    /// shorthand for `--format json`) reuses this same schema.
+    pub fn print_json(&self, prefix: &str) {
+        println!(
+            "{{\"major\":{},\"minor\":{},\"patch\":{},\"prerelease\":{},\"build\":{},\"prefix\":{},\"is_prerelease\":{}}}",
+            self.major,
+            self.minor,
+            self.patch,
+            json_string(self.prerelease.as_deref()),
+            json_string(self.buildmetadata.as_deref()),
+            json_string(Some(prefix)),
+            self.is_prerelease(),
+        );
+    }
+
     /// Print versions on a sigle line
-    pub fn print_single_line(&self, prefix: String) {
+    pub fn print_single_line(&self, prefix: String, only: Option<RollingTag>) {
         match &self.prerelease {
             Some(prerelease) => {
                 println!(
@@ -88,15 +320,13 @@ impl Semver {
                 );
             }
             None => {
-                print!("{}{},", prefix, &self.major);
-                print!("{}{}.{},", prefix, &self.major, &self.minor);
-                println!("{}{}.{}.{}", prefix, &self.major, &self.minor, &self.patch);
+                println!("{}", self.rolling_tags(&prefix, only).join(","));
             }
         }
     }
 
     /// Print versions on multiple lines
-    pub fn print_multiple_lines(&self, prefix: String) {
+    pub fn print_multiple_lines(&self, prefix: String, only: Option<RollingTag>) {
         match &self.prerelease {
             Some(prerelease) => {
                 println!(
@@ -105,16 +335,126 @@ impl Semver {
                 );
             }
             None => {
-                println!("{}{}", prefix, &self.major);
-                println!("{}{}.{}", prefix, &self.major, &self.minor);
-                println!("{}{}.{}.{}", prefix, &self.major, &self.minor, &self.patch);
+                for tag in self.rolling_tags(&prefix, only) {
+                    println!("{tag}");
+                }
             }
         }
     }
 
-    /// Check if it is comparable with another Semver
-    pub fn is_comparable_with(&self, other: &Self) -> bool {
-        self.partial_cmp(other).is_some()
+    /// Check if it is comparable with another Semver.
+    ///
+    /// Precedence is fully defined over major/minor/patch/prerelease, and build
+    /// metadata affects neither it nor equality, so every pair of versions is
+    /// comparable. Kept for API stability now that the partial-order quirk it
+    /// used to report no longer exists.
+    pub fn is_comparable_with(&self, _other: &Self) -> bool {
+        true
+    }
+
+    /// Bump the major version, resetting minor and patch to 0 and clearing
+    /// prerelease and buildmetadata
+    pub fn increment_major(&self) -> Self {
+        Semver {
+            major: self.major + 1,
+            minor: 0,
+            patch: 0,
+            prerelease: None,
+            buildmetadata: None,
+        }
+    }
+
+    /// Bump the minor version, resetting patch to 0 and clearing prerelease
+    /// and buildmetadata
+    pub fn increment_minor(&self) -> Self {
+        Semver {
+            major: self.major,
+            minor: self.minor + 1,
+            patch: 0,
+            prerelease: None,
+            buildmetadata: None,
+        }
+    }
+
+    /// Bump the patch version, clearing prerelease and buildmetadata
+    pub fn increment_patch(&self) -> Self {
+        Semver {
+            major: self.major,
+            minor: self.minor,
+            patch: self.patch + 1,
+            prerelease: None,
+            buildmetadata: None,
+        }
+    }
+
+    /// Move into (or further along) the `alpha` prerelease phase
+    pub fn increment_alpha(&self) -> anyhow::Result<Self> {
+        self.increment_prerelease_phase("alpha")
+    }
+
+    /// Move into (or further along) the `beta` prerelease phase
+    pub fn increment_beta(&self) -> anyhow::Result<Self> {
+        self.increment_prerelease_phase("beta")
+    }
+
+    /// Move into (or further along) the `rc` prerelease phase
+    pub fn increment_rc(&self) -> anyhow::Result<Self> {
+        self.increment_prerelease_phase("rc")
+    }
+
+    /// Bump into the given prerelease phase (`alpha` < `beta` < `rc`).
+    ///
+    /// If the version is already in that phase, the trailing numeric
+    /// identifier is incremented. If it is in an earlier phase, the version
+    /// switches into the requested phase at `.1`. A normal release bumps to
+    /// `<next-patch>-<phase>.1`. Moving backwards in phase precedence
+    /// (e.g. `rc` -> `beta`) is an error.
+    fn increment_prerelease_phase(&self, phase: &str) -> anyhow::Result<Self> {
+        const PHASES: [&str; 3] = ["alpha", "beta", "rc"];
+        let target = PHASES
+            .iter()
+            .position(|&p| p == phase)
+            .expect("phase must be one of alpha, beta, rc");
+
+        match &self.prerelease {
+            Some(prerelease) => {
+                let mut parts = prerelease.splitn(2, '.');
+                let current_phase = parts.next().unwrap_or_default();
+                let current = PHASES
+                    .iter()
+                    .position(|&p| p == current_phase)
+                    .with_context(|| format!("unrecognized prerelease phase: {current_phase}"))?;
+
+                match current.cmp(&target) {
+                    std::cmp::Ordering::Equal => {
+                        // a bare phase with no trailing counter (e.g. `-alpha`)
+                        // is implicitly `.0`, so bumping it yields `.1`
+                        let n: u128 = match parts.next() {
+                            Some(n) => n.parse().context("prerelease identifier is not numeric")?,
+                            None => 0,
+                        };
+                        Ok(Semver {
+                            prerelease: Some(format!("{phase}.{}", n + 1)),
+                            ..self.clone()
+                        })
+                    }
+                    std::cmp::Ordering::Less => Ok(Semver {
+                        prerelease: Some(format!("{phase}.1")),
+                        ..self.clone()
+                    }),
+                    std::cmp::Ordering::Greater => Err(anyhow::anyhow!(
+                        "cannot move backwards from prerelease phase `{current_phase}` to `{phase}`"
+                    )),
+                }
+            }
+            None => Ok(Semver {
+                major: self.major,
+                minor: self.minor,
+                patch: self.patch + 1,
+                prerelease: Some(format!("{phase}.1")),
+                buildmetadata: None,
+            }),
+        }
     }
 }
 
@@ -126,6 +466,16 @@ impl FromStr for Semver {
     }
 }
 
+/// Render an optional string value as a JSON string or `null`. Used for the
+/// grammar-constrained prerelease/buildmetadata fields as well as the
+/// free-form `--prefix` value, so quotes and backslashes are escaped.
+fn json_string(value: Option<&str>) -> String {
+    match value {
+        Some(value) => format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")),
+        None => "null".to_string(),
+    }
+}
+
 pub fn parse(version: &str) -> anyhow::Result<Semver> {
     let caps = RE.captures(version).context("invalid semver")?;
 
@@ -151,6 +501,38 @@ pub fn parse(version: &str) -> anyhow::Result<Semver> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_display_roundtrip() -> anyhow::Result<()> {
+        for s in [
+            "1.2.3",
+            "v1.2.3",
+            "1.2.3-alpha.1",
+            "1.2.3+meta",
+            "1.2.3-alpha.1+meta",
+        ] {
+            let v = parse(s)?;
+            assert_eq!(parse(&v.to_string())?, v);
+        }
+
+        assert_eq!(parse("1.2.3-alpha+meta")?.to_string(), "1.2.3-alpha+meta");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new() -> anyhow::Result<()> {
+        let v = Semver::new(1, 2, 3, Some("alpha.1".to_string()), Some("meta".to_string()))?;
+        assert_eq!(v, parse("1.2.3-alpha.1+meta")?);
+
+        assert!(Semver::new(1, 2, 3, Some("01".to_string()), None).is_err());
+        assert!(Semver::new(1, 2, 3, Some("alpha!".to_string()), None).is_err());
+        assert!(Semver::new(1, 2, 3, None, Some("meta!".to_string())).is_err());
+        assert!(Semver::new(1, 2, 3, Some("0".to_string()), None).is_ok());
+        assert!(Semver::new(1, 2, 3, None, Some("01".to_string())).is_ok());
+
+        Ok(())
+    }
+
     #[test]
     fn test_from_str() -> anyhow::Result<()> {
         let v1 = Semver::from_str("v1.2.3")?;
@@ -172,6 +554,7 @@ mod tests {
         let v4 = parse("1.2.4-test")?;
         let v5 = parse("1.2.4-test+meta")?;
         let v6 = parse("1.2.4-test+meta")?;
+        // build metadata is not significant to equality
         let v7 = parse("1.2.4-test+meta2")?;
 
         assert_eq!(v1, v2);
@@ -179,7 +562,7 @@ mod tests {
         assert_ne!(v2, v3);
         assert_ne!(v3, v4);
         assert_eq!(v5, v6);
-        assert_ne!(v6, v7);
+        assert_eq!(v6, v7);
         assert_ne!(v7, v1);
 
         Ok(())
@@ -218,12 +601,126 @@ mod tests {
         assert!(v5 >= v3);
         assert!(v6 <= v7);
         assert!(v6 >= v7);
-        assert!(v7.partial_cmp(&v8).is_none());
+        assert!(v6 == v7);
+        assert!(v7 < v8);
+        assert!(v8 > v7);
 
         assert!(v1.is_comparable_with(&v2));
         assert!(v1.is_comparable_with(&v3));
         assert!(v6.is_comparable_with(&v7));
-        assert!(!v7.is_comparable_with(&v8));
+        assert!(v7.is_comparable_with(&v8));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_buildmetadata_ignored_for_ordering() -> anyhow::Result<()> {
+        let v1 = parse("1.2.3+build.1")?;
+        let v2 = parse("1.2.3+build.2")?;
+
+        assert_eq!(v1.cmp(&v2), std::cmp::Ordering::Equal);
+        assert_eq!(v1, v2);
+        assert!(v1.is_comparable_with(&v2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort() -> anyhow::Result<()> {
+        let mut versions = vec![
+            parse("1.0.0")?,
+            parse("1.0.0-rc.1")?,
+            parse("1.0.0-alpha")?,
+            parse("1.0.0-alpha.1")?,
+            parse("1.0.0-beta")?,
+        ];
+        versions.sort();
+
+        assert_eq!(
+            versions,
+            vec![
+                parse("1.0.0-alpha")?,
+                parse("1.0.0-alpha.1")?,
+                parse("1.0.0-beta")?,
+                parse("1.0.0-rc.1")?,
+                parse("1.0.0")?,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rolling_tags() -> anyhow::Result<()> {
+        let v = parse("1.2.3")?;
+
+        assert_eq!(v.rolling_tags("v", None), vec!["v1", "v1.2", "v1.2.3"]);
+        assert_eq!(v.rolling_tags("v", Some(RollingTag::Major)), vec!["v1"]);
+        assert_eq!(v.rolling_tags("v", Some(RollingTag::Minor)), vec!["v1.2"]);
+        assert_eq!(
+            v.rolling_tags("v", Some(RollingTag::Patch)),
+            vec!["v1.2.3"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spec_precedence_chain() -> anyhow::Result<()> {
+        let chain = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ]
+        .map(parse)
+        .into_iter()
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+        for pair in chain.windows(2) {
+            assert!(pair[0] < pair[1], "{} should be < {}", pair[0], pair[1]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_increment_major_minor_patch() -> anyhow::Result<()> {
+        let v = parse("1.2.3-alpha.1+meta")?;
+
+        assert_eq!(v.increment_major(), parse("2.0.0")?);
+        assert_eq!(v.increment_minor(), parse("1.3.0")?);
+        assert_eq!(v.increment_patch(), parse("1.2.4")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_increment_prerelease() -> anyhow::Result<()> {
+        let release = parse("1.2.3")?;
+        assert_eq!(release.increment_alpha()?, parse("1.2.4-alpha.1")?);
+        assert_eq!(release.increment_beta()?, parse("1.2.4-beta.1")?);
+        assert_eq!(release.increment_rc()?, parse("1.2.4-rc.1")?);
+
+        let alpha = parse("1.2.3-alpha.1")?;
+        assert_eq!(alpha.increment_alpha()?, parse("1.2.3-alpha.2")?);
+        assert_eq!(alpha.increment_beta()?, parse("1.2.3-beta.1")?);
+        assert_eq!(alpha.increment_rc()?, parse("1.2.3-rc.1")?);
+
+        // a bare phase with no trailing counter is implicitly `.0`
+        let bare_alpha = parse("1.2.3-alpha")?;
+        assert_eq!(bare_alpha.increment_alpha()?, parse("1.2.3-alpha.1")?);
+
+        let beta = parse("1.2.3-beta.4")?;
+        assert_eq!(beta.increment_beta()?, parse("1.2.3-beta.5")?);
+        assert!(beta.increment_alpha().is_err());
+
+        let rc = parse("1.2.3-rc.2")?;
+        assert_eq!(rc.increment_rc()?, parse("1.2.3-rc.3")?);
+        assert!(rc.increment_alpha().is_err());
+        assert!(rc.increment_beta().is_err());
 
         Ok(())
     }
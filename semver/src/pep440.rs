@@ -0,0 +1,218 @@
+use anyhow::Context;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+
+lazy_static! {
+    static ref RE: Regex = Regex::new(
+        r"(?xi)
+            ^
+            (?:(?P<epoch>[0-9]+)!)?
+            (?P<release>[0-9]+(?:\.[0-9]+)*)
+            (?:[-_.]?(?P<pre_label>alpha|beta|preview|pre|a|b|c|rc)[-_.]?(?P<pre_n>[0-9]+)?)?
+            (?:[-_.]?(?P<post>post)(?:[-_.]?(?P<post_n>[0-9]+))?)?
+            (?:[-_.]?(?P<dev>dev)(?:[-_.]?(?P<dev_n>[0-9]+))?)?
+            (?:\+(?P<local>[a-z0-9]+(?:[-_.][a-z0-9]+)*))?
+            $",
+    )
+    .unwrap();
+}
+
+/// A PEP 440 prerelease phase, normalized to its canonical one/two-letter spelling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Phase::Alpha => "a",
+            Phase::Beta => "b",
+            Phase::Rc => "rc",
+        };
+        write!(f, "{label}")
+    }
+}
+
+fn normalize_phase(label: &str) -> Phase {
+    match label.to_ascii_lowercase().as_str() {
+        "alpha" | "a" => Phase::Alpha,
+        "beta" | "b" => Phase::Beta,
+        "c" | "pre" | "preview" | "rc" => Phase::Rc,
+        _ => unreachable!("regex only captures known phase spellings"),
+    }
+}
+
+/// A PEP 440 version: `[N!]N(.N)*[{a|b|rc}N][.postN][.devN][+local]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pep440 {
+    epoch: u64,
+    release: Vec<u64>,
+    pre: Option<(Phase, u64)>,
+    post: Option<u64>,
+    dev: Option<u64>,
+    local: Option<String>,
+}
+
+impl FromStr for Pep440 {
+    type Err = anyhow::Error;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        parse(version)
+    }
+}
+
+impl fmt::Display for Pep440 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.epoch > 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+
+        let release = self
+            .release
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(".");
+        write!(f, "{release}")?;
+
+        if let Some((phase, n)) = &self.pre {
+            write!(f, "{phase}{n}")?;
+        }
+        if let Some(post) = self.post {
+            write!(f, ".post{post}")?;
+        }
+        if let Some(dev) = self.dev {
+            write!(f, ".dev{dev}")?;
+        }
+        if let Some(local) = &self.local {
+            write!(f, "+{local}")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn parse(version: &str) -> anyhow::Result<Pep440> {
+    let caps = RE.captures(version).context("invalid PEP 440 version")?;
+
+    let epoch = caps.name("epoch").map_or(Ok(0), |m| m.as_str().parse())?;
+    let release = caps["release"]
+        .split('.')
+        .map(|n| n.parse().context("invalid release component"))
+        .collect::<anyhow::Result<Vec<u64>>>()?;
+    let pre = caps.name("pre_label").map(|label| {
+        let n = caps
+            .name("pre_n")
+            .map_or(Ok(0), |m| m.as_str().parse())
+            .unwrap_or(0);
+        (normalize_phase(label.as_str()), n)
+    });
+    let post = caps.name("post").map(|_| {
+        caps.name("post_n")
+            .map_or(Ok(0), |m| m.as_str().parse())
+            .unwrap_or(0)
+    });
+    let dev = caps.name("dev").map(|_| {
+        caps.name("dev_n")
+            .map_or(Ok(0), |m| m.as_str().parse())
+            .unwrap_or(0)
+    });
+    // PEP 440 normalizes local-segment separators (`-`/`_`) to `.`
+    let local = caps
+        .name("local")
+        .map(|m| m.as_str().to_ascii_lowercase().replace(['-', '_'], "."));
+
+    Ok(Pep440 {
+        epoch,
+        release,
+        pre,
+        post,
+        dev,
+        local,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release() -> anyhow::Result<()> {
+        let v = parse("1.2.3")?;
+        assert_eq!(v.epoch, 0);
+        assert_eq!(v.release, vec![1, 2, 3]);
+        assert_eq!(v.pre, None);
+        assert_eq!(v.post, None);
+        assert_eq!(v.dev, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_epoch() -> anyhow::Result<()> {
+        let v = parse("1!2.3")?;
+        assert_eq!(v.epoch, 1);
+        assert_eq!(v.release, vec![2, 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalizes_prerelease_spelling() -> anyhow::Result<()> {
+        assert_eq!(parse("1.0alpha1")?.to_string(), "1.0a1");
+        assert_eq!(parse("1.0.beta.2")?.to_string(), "1.0b2");
+        assert_eq!(parse("1.0c1")?.to_string(), "1.0rc1");
+        assert_eq!(parse("1.0pre1")?.to_string(), "1.0rc1");
+        assert_eq!(parse("1.0preview1")?.to_string(), "1.0rc1");
+        assert_eq!(parse("1.0rc1")?.to_string(), "1.0rc1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_post_and_dev() -> anyhow::Result<()> {
+        let v = parse("1.2.3.post1")?;
+        assert_eq!(v.post, Some(1));
+
+        let v = parse("1.2.3.dev4")?;
+        assert_eq!(v.dev, Some(4));
+
+        let v = parse("1.2.3.post1.dev4")?;
+        assert_eq!(v.post, Some(1));
+        assert_eq!(v.dev, Some(4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local() -> anyhow::Result<()> {
+        let v = parse("1.2.3+ubuntu.1")?;
+        assert_eq!(v.local.as_deref(), Some("ubuntu.1"));
+        assert_eq!(v.to_string(), "1.2.3+ubuntu.1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_separators_normalized() -> anyhow::Result<()> {
+        assert_eq!(parse("1.2.3+ubuntu-1")?.to_string(), "1.2.3+ubuntu.1");
+        assert_eq!(parse("1.2.3+ubuntu_1")?.to_string(), "1.2.3+ubuntu.1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_round_trip() -> anyhow::Result<()> {
+        for s in ["1.2.3", "1!1.2.3a1", "1.2.3rc1.post1.dev1+local.1"] {
+            let v = parse(s)?;
+            assert_eq!(parse(&v.to_string())?, v);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,393 @@
+use anyhow::{bail, Context};
+use std::str::FromStr;
+
+use crate::Semver;
+
+/// A single partial version as it appears inside a requirement string, e.g.
+/// the `1`, `1.2` or `1.2.3` in `^1.2`, `~1.2.3` or `1.2.*`.
+struct PartialVersion {
+    major: u128,
+    minor: Option<u128>,
+    patch: Option<u128>,
+    prerelease: Option<String>,
+}
+
+impl FromStr for PartialVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (core, prerelease) = match s.split_once('-') {
+            Some((core, prerelease)) => (core, Some(prerelease.to_string())),
+            None => (s, None),
+        };
+
+        let mut parts = core.split('.');
+        let major = match parts.next().context("missing major version")? {
+            "*" | "x" | "X" => bail!("a wildcard major version is only accepted as a bare `*` requirement"),
+            major => major.parse().context("invalid major version")?,
+        };
+        let minor = match parts.next() {
+            None | Some("*") | Some("x") | Some("X") => None,
+            Some(minor) => Some(minor.parse().context("invalid minor version")?),
+        };
+        let patch = match parts.next() {
+            None | Some("*") | Some("x") | Some("X") => None,
+            Some(patch) => Some(patch.parse().context("invalid patch version")?),
+        };
+        if parts.next().is_some() {
+            bail!("too many version components in `{s}`");
+        }
+
+        Ok(PartialVersion {
+            major,
+            minor,
+            patch,
+            prerelease,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Op {
+    Exact,
+    NotEqual,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+}
+
+#[derive(Debug)]
+struct Comparator {
+    op: Op,
+    version: Semver,
+}
+
+impl Comparator {
+    fn matches_core(&self, version: &Semver) -> bool {
+        let ord = version.cmp(&self.version);
+
+        match self.op {
+            Op::Exact => version == &self.version,
+            Op::NotEqual => version != &self.version,
+            Op::Greater => ord.is_gt(),
+            Op::GreaterEq => ord.is_ge(),
+            Op::Less => ord.is_lt(),
+            Op::LessEq => ord.is_le(),
+        }
+    }
+
+    /// Whether this comparator itself carries a prerelease on the same
+    /// major.minor.patch as `version`, which is what permits a prerelease
+    /// version to satisfy the requirement at all.
+    fn allows_prerelease_of(&self, version: &Semver) -> bool {
+        self.version.prerelease.is_some()
+            && self.version.major == version.major
+            && self.version.minor == version.minor
+            && self.version.patch == version.patch
+    }
+}
+
+/// A version requirement, e.g. `^1.2`, `~1.2.3`, `>=1.2.3, <2.0.0` or `1.2.*`.
+///
+/// A requirement is an OR of comparator sets (split on `||`), each of which
+/// is an AND of comparators (split on `,` or whitespace).
+#[derive(Debug)]
+pub struct VersionReq {
+    sets: Vec<Vec<Comparator>>,
+}
+
+impl VersionReq {
+    /// Check whether `version` satisfies this requirement.
+    pub fn matches(&self, version: &Semver) -> bool {
+        self.sets.iter().any(|set| set_matches(set, version))
+    }
+}
+
+fn set_matches(comparators: &[Comparator], version: &Semver) -> bool {
+    if !comparators.iter().all(|c| c.matches_core(version)) {
+        return false;
+    }
+
+    version.prerelease.is_none() || comparators.iter().any(|c| c.allows_prerelease_of(version))
+}
+
+impl FromStr for VersionReq {
+    type Err = anyhow::Error;
+
+    fn from_str(req: &str) -> Result<Self, Self::Err> {
+        let mut sets = Vec::new();
+        for set in req.split("||") {
+            let mut comparators = Vec::new();
+            for part in set.split(|c: char| c == ',' || c.is_whitespace()) {
+                let part = part.trim();
+                if part.is_empty() || part == "*" {
+                    continue;
+                }
+                parse_comparator_set(part, &mut comparators)?;
+            }
+            sets.push(comparators);
+        }
+
+        Ok(VersionReq { sets })
+    }
+}
+
+pub fn parse(req: &str) -> anyhow::Result<VersionReq> {
+    req.parse()
+}
+
+fn parse_comparator_set(part: &str, comparators: &mut Vec<Comparator>) -> anyhow::Result<()> {
+    if let Some(rest) = part.strip_prefix("^") {
+        return push_caret(rest.parse()?, comparators);
+    }
+    if let Some(rest) = part.strip_prefix("~") {
+        return push_tilde(rest.parse()?, comparators);
+    }
+    if let Some(rest) = part.strip_prefix(">=") {
+        comparators.push(Comparator {
+            op: Op::GreaterEq,
+            version: rest.trim().parse()?,
+        });
+        return Ok(());
+    }
+    if let Some(rest) = part.strip_prefix("<=") {
+        comparators.push(Comparator {
+            op: Op::LessEq,
+            version: rest.trim().parse()?,
+        });
+        return Ok(());
+    }
+    if let Some(rest) = part.strip_prefix("!=") {
+        comparators.push(Comparator {
+            op: Op::NotEqual,
+            version: rest.trim().parse()?,
+        });
+        return Ok(());
+    }
+    if let Some(rest) = part.strip_prefix('>') {
+        comparators.push(Comparator {
+            op: Op::Greater,
+            version: rest.trim().parse()?,
+        });
+        return Ok(());
+    }
+    if let Some(rest) = part.strip_prefix('<') {
+        comparators.push(Comparator {
+            op: Op::Less,
+            version: rest.trim().parse()?,
+        });
+        return Ok(());
+    }
+    if let Some(rest) = part.strip_prefix('=') {
+        return push_wildcard(rest.trim().parse()?, comparators);
+    }
+
+    push_wildcard(part.parse()?, comparators)
+}
+
+/// `^1.2.3` => `>=1.2.3, <2.0.0`, freezing the left-most non-zero component.
+fn push_caret(partial: PartialVersion, comparators: &mut Vec<Comparator>) -> anyhow::Result<()> {
+    let minor = partial.minor.unwrap_or(0);
+    let patch = partial.patch.unwrap_or(0);
+
+    let (upper_major, upper_minor, upper_patch) = if partial.major > 0 {
+        (partial.major + 1, 0, 0)
+    } else if minor > 0 {
+        (0, minor + 1, 0)
+    } else {
+        (0, 0, patch + 1)
+    };
+
+    comparators.push(Comparator {
+        op: Op::GreaterEq,
+        version: full_version(partial.major, minor, patch, partial.prerelease)?,
+    });
+    comparators.push(Comparator {
+        op: Op::Less,
+        version: full_version(upper_major, upper_minor, upper_patch, None)?,
+    });
+    Ok(())
+}
+
+/// `~1.2.3` => `>=1.2.3, <1.3.0`; `~1.2` => `>=1.2.0, <1.3.0`; `~1` => `>=1.0.0, <2.0.0`.
+fn push_tilde(partial: PartialVersion, comparators: &mut Vec<Comparator>) -> anyhow::Result<()> {
+    let minor = partial.minor.unwrap_or(0);
+    let patch = partial.patch.unwrap_or(0);
+
+    // With no minor component given, the tilde only pins the major version.
+    let (upper_major, upper_minor) = match partial.minor {
+        Some(minor) => (partial.major, minor + 1),
+        None => (partial.major + 1, 0),
+    };
+
+    comparators.push(Comparator {
+        op: Op::GreaterEq,
+        version: full_version(partial.major, minor, patch, partial.prerelease)?,
+    });
+    comparators.push(Comparator {
+        op: Op::Less,
+        version: full_version(upper_major, upper_minor, 0, None)?,
+    });
+    Ok(())
+}
+
+/// A bare partial/wildcard version (`1.2.*`, `1.*`, `1`, or a full `1.2.3`).
+/// A bare `*` requirement is skipped by the parser and never gets here.
+fn push_wildcard(partial: PartialVersion, comparators: &mut Vec<Comparator>) -> anyhow::Result<()> {
+    match (partial.minor, partial.patch) {
+        (Some(minor), Some(patch)) => comparators.push(Comparator {
+            op: Op::Exact,
+            version: full_version(partial.major, minor, patch, partial.prerelease)?,
+        }),
+        (Some(minor), None) => {
+            comparators.push(Comparator {
+                op: Op::GreaterEq,
+                version: full_version(partial.major, minor, 0, None)?,
+            });
+            comparators.push(Comparator {
+                op: Op::Less,
+                version: full_version(partial.major, minor + 1, 0, None)?,
+            });
+        }
+        (None, _) => {
+            comparators.push(Comparator {
+                op: Op::GreaterEq,
+                version: full_version(partial.major, 0, 0, None)?,
+            });
+            comparators.push(Comparator {
+                op: Op::Less,
+                version: full_version(partial.major + 1, 0, 0, None)?,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn full_version(
+    major: u128,
+    minor: u128,
+    patch: u128,
+    prerelease: Option<String>,
+) -> anyhow::Result<Semver> {
+    let prerelease = match prerelease {
+        Some(prerelease) => format!("-{prerelease}"),
+        None => String::new(),
+    };
+    format!("{major}.{minor}.{patch}{prerelease}").parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse as parse_version;
+
+    #[test]
+    fn test_comparator_list() -> anyhow::Result<()> {
+        let req: VersionReq = ">=1.2.3, <2.0.0".parse()?;
+
+        assert!(req.matches(&parse_version("1.2.3")?));
+        assert!(req.matches(&parse_version("1.9.9")?));
+        assert!(!req.matches(&parse_version("1.2.2")?));
+        assert!(!req.matches(&parse_version("2.0.0")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_exact_and_not_equal() -> anyhow::Result<()> {
+        let exact: VersionReq = "=1.0.0".parse()?;
+        assert!(exact.matches(&parse_version("1.0.0")?));
+        assert!(!exact.matches(&parse_version("1.0.1")?));
+
+        let not_equal: VersionReq = "!=1.5.0".parse()?;
+        assert!(not_equal.matches(&parse_version("1.4.0")?));
+        assert!(!not_equal.matches(&parse_version("1.5.0")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_caret() -> anyhow::Result<()> {
+        let req: VersionReq = "^1.2.3".parse()?;
+        assert!(req.matches(&parse_version("1.4.2")?));
+        assert!(!req.matches(&parse_version("2.0.0")?));
+        assert!(!req.matches(&parse_version("1.2.2")?));
+
+        let req: VersionReq = "^0.2.3".parse()?;
+        assert!(req.matches(&parse_version("0.2.9")?));
+        assert!(!req.matches(&parse_version("0.3.0")?));
+
+        let req: VersionReq = "^0.0.3".parse()?;
+        assert!(req.matches(&parse_version("0.0.3")?));
+        assert!(!req.matches(&parse_version("0.0.4")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tilde() -> anyhow::Result<()> {
+        let req: VersionReq = "~1.2.3".parse()?;
+        assert!(req.matches(&parse_version("1.2.9")?));
+        assert!(!req.matches(&parse_version("1.3.0")?));
+
+        let req: VersionReq = "~1.2".parse()?;
+        assert!(req.matches(&parse_version("1.2.0")?));
+        assert!(!req.matches(&parse_version("1.3.0")?));
+
+        let req: VersionReq = "~1".parse()?;
+        assert!(req.matches(&parse_version("1.5.0")?));
+        assert!(!req.matches(&parse_version("2.0.0")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wildcard() -> anyhow::Result<()> {
+        let req: VersionReq = "1.2.*".parse()?;
+        assert!(req.matches(&parse_version("1.2.5")?));
+        assert!(!req.matches(&parse_version("1.3.0")?));
+
+        let req: VersionReq = "1.*".parse()?;
+        assert!(req.matches(&parse_version("1.9.9")?));
+        assert!(!req.matches(&parse_version("2.0.0")?));
+
+        let req: VersionReq = "*".parse()?;
+        assert!(req.matches(&parse_version("1.9.9")?));
+        assert!(req.matches(&parse_version("2.0.0")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prerelease_only_matches_matching_comparator() -> anyhow::Result<()> {
+        let req: VersionReq = ">=1.0.0-alpha, <2.0.0".parse()?;
+        assert!(!req.matches(&parse_version("1.5.0-beta")?));
+
+        let req: VersionReq = ">=1.2.3-alpha, <1.2.3".parse()?;
+        assert!(req.matches(&parse_version("1.2.3-alpha")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_space_separated_and() -> anyhow::Result<()> {
+        let req: VersionReq = ">=1.2.3 <2.0.0".parse()?;
+
+        assert!(req.matches(&parse_version("1.5.0")?));
+        assert!(!req.matches(&parse_version("2.0.0")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_or() -> anyhow::Result<()> {
+        let req: VersionReq = "^1.2.3 || ^2.0.0".parse()?;
+
+        assert!(req.matches(&parse_version("1.4.0")?));
+        assert!(req.matches(&parse_version("2.1.0")?));
+        assert!(!req.matches(&parse_version("3.0.0")?));
+
+        Ok(())
+    }
+}